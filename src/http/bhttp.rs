@@ -0,0 +1,254 @@
+//! RFC 9292 Binary HTTP (known-length form) encoding and decoding.
+
+use anyhow::{Result, anyhow, bail};
+
+use super::{Headers, Request, Response};
+
+const FRAMING_INDICATOR_REQUEST: u64 = 0;
+const FRAMING_INDICATOR_RESPONSE: u64 = 1;
+
+impl Request {
+    pub fn encode_bhttp(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode_varint(FRAMING_INDICATOR_REQUEST, &mut buf);
+
+        encode_length_prefixed(self.method.as_bytes(), &mut buf);
+        encode_length_prefixed(self.scheme.as_bytes(), &mut buf);
+        let authority = self.headers.get("host").cloned().unwrap_or_default();
+        encode_length_prefixed(authority.as_bytes(), &mut buf);
+        encode_length_prefixed(self.path.as_bytes(), &mut buf);
+
+        encode_field_section(&self.headers, &mut buf);
+        encode_length_prefixed(self.body.as_deref().unwrap_or(&[]), &mut buf);
+        encode_length_prefixed(&[], &mut buf); // empty trailer section
+
+        buf
+    }
+
+    pub fn decode_bhttp(bytes: &[u8]) -> Result<Self> {
+        let mut pos = 0;
+
+        let framing = decode_varint(bytes, &mut pos)?;
+        if framing != FRAMING_INDICATOR_REQUEST {
+            bail!("Expected a known-length bhttp request, got framing indicator {framing}");
+        }
+
+        let method = decode_string(bytes, &mut pos)?;
+        let scheme = decode_string(bytes, &mut pos)?;
+        let authority = decode_string(bytes, &mut pos)?;
+        let path = decode_string(bytes, &mut pos)?;
+
+        let mut headers = decode_field_section(bytes, &mut pos)?;
+        if !authority.is_empty() {
+            headers.insert("Host", &authority);
+        }
+
+        let body = decode_length_prefixed(bytes, &mut pos)?.to_vec();
+        decode_field_section(bytes, &mut pos)?; // trailer section, discarded
+
+        Ok(Request {
+            method,
+            scheme,
+            path,
+            headers,
+            body: if body.is_empty() { None } else { Some(body) },
+        })
+    }
+}
+
+impl Response {
+    pub fn encode_bhttp(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode_varint(FRAMING_INDICATOR_RESPONSE, &mut buf);
+        encode_varint(self.status_code as u64, &mut buf);
+
+        encode_field_section(&self.headers, &mut buf);
+        encode_length_prefixed(&self.body, &mut buf);
+        encode_length_prefixed(&[], &mut buf); // empty trailer section
+
+        buf
+    }
+
+    pub fn decode_bhttp(bytes: &[u8]) -> Result<Self> {
+        let mut pos = 0;
+
+        let framing = decode_varint(bytes, &mut pos)?;
+        if framing != FRAMING_INDICATOR_RESPONSE {
+            bail!("Expected a known-length bhttp response, got framing indicator {framing}");
+        }
+
+        let status_code = decode_varint(bytes, &mut pos)?;
+        if (100..200).contains(&status_code) {
+            bail!("Informational (1xx) bhttp responses are not supported");
+        }
+
+        let headers = decode_field_section(bytes, &mut pos)?;
+        let body = decode_length_prefixed(bytes, &mut pos)?.to_vec();
+        decode_field_section(bytes, &mut pos)?; // trailer section, discarded
+
+        Ok(Response {
+            status_code: status_code as u16,
+            status_message: String::new(),
+            headers,
+            body,
+            decoded_body: None,
+            url: None,
+            streaming: None,
+        })
+    }
+}
+
+/// Encodes `value` as a QUIC-style variable-length integer (RFC 9000 section 16).
+fn encode_varint(value: u64, buf: &mut Vec<u8>) {
+    if value <= 0x3f {
+        buf.push(value as u8);
+    } else if value <= 0x3fff {
+        buf.extend_from_slice(&(value as u16 | 0x4000).to_be_bytes());
+    } else if value <= 0x3fff_ffff {
+        buf.extend_from_slice(&(value as u32 | 0x8000_0000).to_be_bytes());
+    } else if value <= 0x3fff_ffff_ffff_ffff {
+        buf.extend_from_slice(&(value | 0xc000_0000_0000_0000).to_be_bytes());
+    } else {
+        panic!("varint value {value} does not fit in 62 bits");
+    }
+}
+
+fn decode_varint(bytes: &[u8], pos: &mut usize) -> Result<u64> {
+    let first = *bytes
+        .get(*pos)
+        .ok_or_else(|| anyhow!("Unexpected end of bhttp message while reading varint"))?;
+    let len = 1usize << (first >> 6);
+
+    let field = bytes
+        .get(*pos..*pos + len)
+        .ok_or_else(|| anyhow!("Unexpected end of bhttp message while reading varint"))?;
+    let mut value = (first & 0x3f) as u64;
+    for &byte in &field[1..] {
+        value = (value << 8) | byte as u64;
+    }
+
+    *pos += len;
+    Ok(value)
+}
+
+fn encode_length_prefixed(bytes: &[u8], buf: &mut Vec<u8>) {
+    encode_varint(bytes.len() as u64, buf);
+    buf.extend_from_slice(bytes);
+}
+
+fn decode_length_prefixed<'a>(bytes: &'a [u8], pos: &mut usize) -> Result<&'a [u8]> {
+    let len = decode_varint(bytes, pos)? as usize;
+    let slice = bytes
+        .get(*pos..*pos + len)
+        .ok_or_else(|| anyhow!("Unexpected end of bhttp message while reading length-prefixed field"))?;
+    *pos += len;
+    Ok(slice)
+}
+
+fn decode_string(bytes: &[u8], pos: &mut usize) -> Result<String> {
+    Ok(String::from_utf8(decode_length_prefixed(bytes, pos)?.to_vec())?)
+}
+
+fn encode_field_section(headers: &Headers, buf: &mut Vec<u8>) {
+    let mut section = Vec::new();
+    for (key, value) in headers.iter() {
+        encode_length_prefixed(key.as_bytes(), &mut section);
+        encode_length_prefixed(value.as_bytes(), &mut section);
+    }
+    encode_length_prefixed(&section, buf);
+}
+
+fn decode_field_section(bytes: &[u8], pos: &mut usize) -> Result<Headers> {
+    let section = decode_length_prefixed(bytes, pos)?;
+    let mut headers = Headers::default();
+
+    let mut section_pos = 0;
+    while section_pos < section.len() {
+        let name = decode_string(section, &mut section_pos)?;
+        let value = decode_string(section, &mut section_pos)?;
+        headers.insert(name, value);
+    }
+
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_roundtrips_through_bhttp() {
+        let mut headers = Headers::default();
+        headers.insert("Host", "example.com");
+        headers.insert("Accept", "text/plain");
+        let request = Request {
+            method: "POST".to_string(),
+            scheme: "https".to_string(),
+            path: "/foo?bar=baz".to_string(),
+            headers,
+            body: Some(b"hello".to_vec()),
+        };
+
+        let decoded = Request::decode_bhttp(&request.encode_bhttp()).unwrap();
+
+        assert_eq!(decoded.method, "POST");
+        assert_eq!(decoded.scheme, "https");
+        assert_eq!(decoded.path, "/foo?bar=baz");
+        assert_eq!(decoded.headers.get("host"), Some(&"example.com".to_string()));
+        assert_eq!(decoded.headers.get("accept"), Some(&"text/plain".to_string()));
+        assert_eq!(decoded.body, Some(b"hello".to_vec()));
+    }
+
+    #[test]
+    fn request_with_no_body_roundtrips_with_none_body() {
+        let request = Request {
+            method: "GET".to_string(),
+            scheme: "http".to_string(),
+            path: "/".to_string(),
+            headers: Headers::default(),
+            body: None,
+        };
+
+        let decoded = Request::decode_bhttp(&request.encode_bhttp()).unwrap();
+        assert_eq!(decoded.body, None);
+    }
+
+    #[test]
+    fn response_roundtrips_through_bhttp() {
+        let mut headers = Headers::default();
+        headers.insert("Content-Type", "text/plain");
+        let response = Response {
+            status_code: 200,
+            status_message: String::new(),
+            headers,
+            body: b"hello".to_vec(),
+            decoded_body: None,
+            url: None,
+            streaming: None,
+        };
+
+        let decoded = Response::decode_bhttp(&response.encode_bhttp()).unwrap();
+
+        assert_eq!(decoded.status_code, 200);
+        assert_eq!(
+            decoded.headers.get("content-type"),
+            Some(&"text/plain".to_string())
+        );
+        assert_eq!(decoded.body, b"hello");
+    }
+
+    #[test]
+    fn decode_bhttp_rejects_informational_status() {
+        let response = Response {
+            status_code: 100,
+            status_message: String::new(),
+            headers: Headers::default(),
+            body: Vec::new(),
+            decoded_body: None,
+            url: None,
+            streaming: None,
+        };
+
+        assert!(Response::decode_bhttp(&response.encode_bhttp()).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+//! Transparent `Content-Encoding` decompression of response bodies.
+
+use std::io::Read;
+
+use anyhow::{Result, bail};
+use flate2::read::{DeflateDecoder, GzDecoder};
+
+/// Decodes `body` according to `content_encoding`, a comma-separated list of
+/// codings in the order they were applied (the same order the
+/// `Content-Encoding` header lists them in), so they're undone in reverse.
+pub(crate) fn decode(content_encoding: &str, body: &[u8]) -> Result<Vec<u8>> {
+    let mut data = body.to_vec();
+
+    for coding in content_encoding.split(',').rev() {
+        data = match coding.trim().to_lowercase().as_str() {
+            "identity" => data,
+            "gzip" | "x-gzip" => decode_with(GzDecoder::new(data.as_slice()))?,
+            "deflate" => decode_with(DeflateDecoder::new(data.as_slice()))?,
+            "br" => decode_brotli(&data)?,
+            other => bail!("Unsupported content-encoding: {other}"),
+        };
+    }
+
+    Ok(data)
+}
+
+fn decode_with(mut decoder: impl Read) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    decoder.read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+fn decode_brotli(data: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    brotli::BrotliDecompress(&mut std::io::Cursor::new(data), &mut decoded)?;
+    Ok(decoded)
+}
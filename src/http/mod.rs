@@ -0,0 +1,901 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow, bail};
+use base64::Engine;
+use url::{Url, form_urlencoded};
+
+mod bhttp;
+mod encoding;
+mod multipart;
+mod pool;
+mod streaming;
+
+pub use multipart::MultipartBuilder;
+pub use streaming::BodyReader;
+
+#[derive(PartialEq)]
+pub enum HttpVersion {
+    Http1_0,
+    Http1_1,
+}
+
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+const DEFAULT_MAX_IDLE_PER_HOST: usize = 4;
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const DEFAULT_ACCEPT_ENCODING: [&str; 3] = ["gzip", "deflate", "br"];
+
+pub struct Client {
+    http_version: HttpVersion,
+    headers: Headers,
+    max_redirects: usize,
+    pool: pool::Pool,
+    decode_content_encoding: bool,
+    accept_encoding: Vec<String>,
+}
+
+impl Client {
+    pub fn new(http_version: HttpVersion) -> Self {
+        Self {
+            http_version,
+            headers: Default::default(),
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            pool: pool::Pool::new(DEFAULT_MAX_IDLE_PER_HOST, DEFAULT_IDLE_TIMEOUT),
+            decode_content_encoding: true,
+            accept_encoding: DEFAULT_ACCEPT_ENCODING.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn headers_mut(&mut self) -> &mut Headers {
+        &mut self.headers
+    }
+
+    pub fn set_max_redirects(&mut self, max_redirects: usize) {
+        self.max_redirects = max_redirects;
+    }
+
+    /// Caps how many idle keep-alive connections are kept around per
+    /// (scheme, host, port). Set to `0` to disable connection reuse.
+    pub fn set_max_idle_per_host(&mut self, max_idle_per_host: usize) {
+        self.pool.set_max_idle_per_host(max_idle_per_host);
+    }
+
+    /// How long an idle keep-alive connection may sit in the pool before
+    /// it's considered stale and reconnected instead of reused.
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.pool.set_idle_timeout(idle_timeout);
+    }
+
+    /// Enables or disables transparently decompressing response bodies
+    /// according to their `Content-Encoding` (on by default). Also controls
+    /// whether an `Accept-Encoding` header is sent.
+    pub fn set_decode_content_encoding(&mut self, enabled: bool) {
+        self.decode_content_encoding = enabled;
+    }
+
+    /// Sets the codecs advertised via `Accept-Encoding`. Defaults to `gzip`,
+    /// `deflate` and `br`. Decoding is not limited to this list: any
+    /// `Content-Encoding` the client supports is decoded.
+    pub fn set_accept_encoding(&mut self, codecs: Vec<String>) {
+        self.accept_encoding = codecs;
+    }
+
+    /// Sets the `Authorization` header to use HTTP Basic authentication with
+    /// the given credentials.
+    pub fn set_basic_auth(&mut self, username: &str, password: &str) {
+        self.headers.insert("Authorization", basic_auth_header(username, password));
+    }
+
+    /// Sets the `Authorization` header to use a Bearer token.
+    pub fn set_bearer_auth(&mut self, token: &str) {
+        self.headers.insert("Authorization", format!("Bearer {token}"));
+    }
+
+    pub fn get(&self, url: &str) -> Result<Response> {
+        self.send("GET", url, None)
+    }
+
+    pub fn post(&self, url: &str, body: Vec<u8>) -> Result<Response> {
+        self.send("POST", url, Some(body))
+    }
+
+    /// Sends a request and returns a [`Response`] whose body can be read
+    /// incrementally via [`Response::body_reader`] instead of being
+    /// buffered in memory up front. Unlike [`Client::send`], this does not
+    /// follow redirects or reuse pooled connections, since the caller owns
+    /// the connection for as long as it keeps reading the body.
+    pub fn send_streaming(&self, method: &str, url: &str, body: Option<Vec<u8>>) -> Result<Response> {
+        let url = Url::parse(url)?;
+        let host = url
+            .host()
+            .ok_or_else(|| anyhow!("Given URL does not contain a host"))?
+            .to_string();
+        let port = url.port().unwrap_or(match url.scheme() {
+            "http" => 80,
+            "https" => 443,
+            _ => bail!("Unknown scheme: {}", url.scheme()),
+        });
+
+        let req = self.build_request(method, &url, &host, body, None);
+
+        let mut request_bytes = Vec::new();
+        match self.http_version {
+            HttpVersion::Http1_0 => req.send_v10(&mut request_bytes)?,
+            HttpVersion::Http1_1 => req.send_v11(&mut request_bytes)?,
+        }
+
+        let mut connection = open_connection(&url, &host, port)?;
+        connection.write_all(&request_bytes)?;
+
+        let mut response = streaming::read_response_head(connection)?;
+        response.set_url(url);
+        Ok(response)
+    }
+
+    /// Sends a `POST` with `pairs` percent-encoded as an
+    /// `application/x-www-form-urlencoded` body.
+    pub fn post_form(&self, url: &str, pairs: &[(&str, &str)]) -> Result<Response> {
+        let body = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish()
+            .into_bytes();
+        self.send_with_content_type("POST", url, Some(body), "application/x-www-form-urlencoded")
+    }
+
+    /// Sends a `POST` with a [`MultipartBuilder`]-assembled
+    /// `multipart/form-data` body.
+    pub fn post_multipart(&self, url: &str, multipart: MultipartBuilder) -> Result<Response> {
+        let (body, content_type) = multipart.build();
+        self.send_with_content_type("POST", url, Some(body), &content_type)
+    }
+
+    /// Sends a request, following 3xx redirects via the `Location` header up
+    /// to `max_redirects` hops.
+    pub fn send(&self, method: &str, url: &str, body: Option<Vec<u8>>) -> Result<Response> {
+        self.send_with_content_type_opt(method, url, body, None)
+    }
+
+    fn send_with_content_type(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<Vec<u8>>,
+        content_type: &str,
+    ) -> Result<Response> {
+        self.send_with_content_type_opt(method, url, body, Some(content_type))
+    }
+
+    fn send_with_content_type_opt(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<Vec<u8>>,
+        content_type: Option<&str>,
+    ) -> Result<Response> {
+        let mut url = Url::parse(url)?;
+        let mut method = method.to_string();
+        let mut body = body;
+        let mut content_type = content_type.map(str::to_string);
+        let mut redirects = 0usize;
+
+        loop {
+            let response = self.send_once(&method, &url, body.clone(), content_type.as_deref())?;
+
+            if !is_redirect(response.status_code()) {
+                return Ok(response);
+            }
+
+            let Some(location) = response.headers().get("Location").cloned() else {
+                return Ok(response);
+            };
+
+            if redirects >= self.max_redirects {
+                bail!("Exceeded the maximum of {} redirects", self.max_redirects);
+            }
+            redirects += 1;
+
+            url = url.join(&location)?;
+            match response.status_code() {
+                303 => {
+                    method = "GET".to_string();
+                    body = None;
+                    content_type = None;
+                }
+                301 | 302 if method != "HEAD" => {
+                    method = "GET".to_string();
+                    body = None;
+                    content_type = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn send_once(
+        &self,
+        method: &str,
+        url: &Url,
+        body: Option<Vec<u8>>,
+        content_type: Option<&str>,
+    ) -> Result<Response> {
+        let host = url
+            .host()
+            .ok_or_else(|| anyhow!("Given URL does not contain a host"))?
+            .to_string();
+        let port = url.port().unwrap_or(match url.scheme() {
+            "http" => 80,
+            "https" => 443,
+            _ => bail!("Unknown scheme: {}", url.scheme()),
+        });
+
+        let mut req = self.build_request(method, url, &host, body, content_type);
+        if self.decode_content_encoding
+            && !self.accept_encoding.is_empty()
+            && !req.headers.contains("Accept-Encoding")
+        {
+            req.headers.insert("Accept-Encoding", self.accept_encoding.join(", "));
+        }
+
+        let mut request_bytes = Vec::new();
+        match self.http_version {
+            HttpVersion::Http1_0 => req.send_v10(&mut request_bytes)?,
+            HttpVersion::Http1_1 => req.send_v11(&mut request_bytes)?,
+        }
+
+        println!(">>> {} bytes", request_bytes.len());
+        println!(
+            "{}",
+            prefix_lines(&String::from_utf8_lossy(&request_bytes), ">>> "),
+        );
+
+        let (response_bytes, mut response) = if self.http_version == HttpVersion::Http1_1 {
+            let key = pool::Key {
+                scheme: url.scheme().to_string(),
+                host: host.clone(),
+                port,
+            };
+            self.send_keep_alive(&key, url, &host, port, &request_bytes)?
+        } else {
+            let mut connection = open_connection(url, &host, port)?;
+            let mut response_bytes = Vec::new();
+            let result = do_read_write(&mut connection, &request_bytes, &mut response_bytes);
+            if let Err(error) = &result {
+                if error.kind() != std::io::ErrorKind::UnexpectedEof {
+                    result?;
+                }
+            }
+            let response = Response::parse(&response_bytes)?;
+            (response_bytes, response)
+        };
+
+        println!();
+        println!("<<< {} bytes", response_bytes.len());
+        println!(
+            "{}",
+            prefix_lines(&String::from_utf8_lossy(&response_bytes), "<<< "),
+        );
+
+        if self.decode_content_encoding {
+            if let Some(content_encoding) = response.headers().get("Content-Encoding").cloned() {
+                let decoded = encoding::decode(&content_encoding, response.raw_body())?;
+                response.set_decoded_body(decoded);
+            }
+        }
+
+        response.set_url(url.clone());
+        Ok(response)
+    }
+
+    /// Reuses a pooled keep-alive connection for `key` if one is available,
+    /// falling back to a fresh connection (and retrying once) if the pooled
+    /// one turns out to be stale. The connection is handed back to the pool
+    /// only once the response has been read with exact framing and that
+    /// framing tells us unambiguously where the next response would start.
+    fn send_keep_alive(
+        &self,
+        key: &pool::Key,
+        url: &Url,
+        host: &str,
+        port: u16,
+        request_bytes: &[u8],
+    ) -> Result<(Vec<u8>, Response)> {
+        if let Some(mut connection) = self.pool.checkout(key) {
+            if let Ok((response_bytes, response, reusable)) =
+                write_and_read(&mut connection, request_bytes)
+            {
+                if reusable && response_keeps_connection_alive(&response) {
+                    self.pool.checkin(key.clone(), connection);
+                }
+                return Ok((response_bytes, response));
+            }
+            // The pooled connection was stale (e.g. the server closed it
+            // after our idle timeout but before we noticed); fall through
+            // to a fresh one below.
+        }
+
+        let mut connection = open_connection(url, host, port)?;
+        let (response_bytes, response, reusable) = write_and_read(&mut connection, request_bytes)?;
+        if reusable && response_keeps_connection_alive(&response) {
+            self.pool.checkin(key.clone(), connection);
+        }
+        Ok((response_bytes, response))
+    }
+
+    /// Builds the [`Request`] for `method`/`url`, applying the client's
+    /// default headers plus `Host`, URL-embedded Basic auth (unless
+    /// `Authorization` is already set), `User-Agent` and, if given, a
+    /// `Content-Type`. Shared by [`Client::send_once`] and
+    /// [`Client::send_streaming`] so the two don't drift apart.
+    fn build_request(
+        &self,
+        method: &str,
+        url: &Url,
+        host: &str,
+        body: Option<Vec<u8>>,
+        content_type: Option<&str>,
+    ) -> Request {
+        let mut headers = self.headers.clone();
+        headers.insert("Host", host);
+        if !headers.contains("Authorization") && !url.username().is_empty() {
+            headers.insert(
+                "Authorization",
+                basic_auth_header(url.username(), url.password().unwrap_or("")),
+            );
+        }
+        if !headers.contains("User-Agent") {
+            headers.insert(
+                "User-Agent",
+                format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            );
+        }
+        if let Some(content_type) = content_type {
+            headers.insert("Content-Type", content_type);
+        }
+
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path = format!("{path}?{query}");
+        }
+
+        Request {
+            method: method.to_string(),
+            scheme: url.scheme().to_string(),
+            path,
+            headers,
+            body,
+        }
+    }
+}
+
+/// Base64-encodes `username:password` into a complete HTTP Basic
+/// `Authorization` header value, including the `Basic ` prefix.
+fn basic_auth_header(username: &str, password: &str) -> String {
+    let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+    format!("Basic {credentials}")
+}
+
+fn is_redirect(status_code: u16) -> bool {
+    matches!(status_code, 301 | 302 | 303 | 307 | 308)
+}
+
+fn response_keeps_connection_alive(response: &Response) -> bool {
+    !response
+        .headers()
+        .get("Connection")
+        .is_some_and(|value| value.eq_ignore_ascii_case("close"))
+}
+
+fn open_connection(url: &Url, host: &str, port: u16) -> Result<pool::Connection> {
+    let tcp = TcpStream::connect_timeout(
+        &format!("{host}:{port}")
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| anyhow!("Failed to resolve given address"))?,
+        Duration::from_secs(30),
+    )?;
+
+    if url.scheme() == "https" {
+        let root_store =
+            rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let config = rustls::ClientConfig::builder()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+        let server_name = host.to_string().try_into()?;
+        let conn = rustls::ClientConnection::new(Arc::new(config), server_name)?;
+        Ok(pool::Connection::Tls(Box::new(rustls::StreamOwned::new(
+            conn, tcp,
+        ))))
+    } else {
+        Ok(pool::Connection::Plain(tcp))
+    }
+}
+
+fn write_and_read(
+    connection: &mut pool::Connection,
+    request_bytes: &[u8],
+) -> Result<(Vec<u8>, Response, bool)> {
+    connection.write_all(request_bytes)?;
+    read_response(connection)
+}
+
+fn do_read_write<S>(
+    mut stream: S,
+    request_bytes: &[u8],
+    response_bytes: &mut Vec<u8>,
+) -> std::io::Result<()>
+where
+    S: Read + Write,
+{
+    stream.write_all(request_bytes)?;
+    //stream.flush()?;
+    stream.read_to_end(response_bytes)?;
+    Ok(())
+}
+
+/// Reads a response off a live, possibly-reused connection by exact framing
+/// (`Content-Length` bytes, or chunked until the terminating `0\r\n\r\n`)
+/// rather than reading to EOF, since a keep-alive socket isn't closed to
+/// signal the end of the body. Returns the raw bytes read, the parsed
+/// response, and whether the framing was exact enough that the connection's
+/// next byte is unambiguously the start of a new response (`false` for the
+/// close-delimited case below, where it never is).
+fn read_response<S: Read>(stream: &mut S) -> Result<(Vec<u8>, Response, bool)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    while find_header_terminator(&buf).is_none() {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            bail!("Connection closed before the response headers were complete");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = parse_head(&buf)?;
+
+    let (body, reusable) = if head.is_chunked {
+        let body = loop {
+            if let Some(body) = try_parse_chunked_body(&buf[head.pos..])? {
+                break body;
+            }
+            let n = stream.read(&mut chunk)?;
+            if n == 0 {
+                bail!("Connection closed before the chunked response body was complete");
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        };
+        (body, true)
+    } else if head.has_content_length {
+        let total_len = head.pos + head.content_length;
+        while buf.len() < total_len {
+            let remaining = total_len - buf.len();
+            let to_read = remaining.min(chunk.len());
+            let n = stream.read(&mut chunk[..to_read])?;
+            if n == 0 {
+                bail!("Connection closed before the response body was complete");
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        (buf[head.pos..total_len].to_vec(), true)
+    } else {
+        // Neither `Content-Length` nor chunked: the only legal framing left
+        // is close-delimited, where the body runs until the server closes
+        // the connection. Read to EOF and make sure this connection is
+        // never checked back into the pool, since there's no way to tell
+        // where the next response would begin on it.
+        loop {
+            let n = stream.read(&mut chunk)?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        (buf[head.pos..].to_vec(), false)
+    };
+
+    let response = Response {
+        status_code: head.status_code,
+        status_message: head.status_message,
+        headers: head.headers,
+        body,
+        decoded_body: None,
+        url: None,
+        streaming: None,
+    };
+    Ok((buf, response, reusable))
+}
+
+fn find_header_terminator(data: &[u8]) -> Option<usize> {
+    data.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+fn find_crlf(data: &[u8]) -> Option<usize> {
+    data.windows(2).position(|window| window == b"\r\n")
+}
+
+/// Tries to decode a complete chunked body out of `data` (the bytes
+/// following the response head). Returns `Ok(None)` if `data` doesn't yet
+/// contain a full, terminated chunked body.
+fn try_parse_chunked_body(data: &[u8]) -> Result<Option<Vec<u8>>> {
+    let mut body = Vec::new();
+    let mut cursor = 0;
+
+    loop {
+        let Some(line_len) = find_crlf(&data[cursor..]) else {
+            return Ok(None);
+        };
+        let octets = std::str::from_utf8(&data[cursor..cursor + line_len])?;
+        let length = usize::from_str_radix(octets, 16)?;
+        let chunk_start = cursor + line_len + 2;
+
+        if length == 0 {
+            let trailer_end = chunk_start + 2;
+            if data.len() < trailer_end {
+                return Ok(None);
+            }
+            if &data[chunk_start..trailer_end] != b"\r\n" {
+                bail!("Chunked response body is missing its trailer terminator");
+            }
+            return Ok(Some(body));
+        }
+
+        let chunk_end = chunk_start + length;
+        if data.len() < chunk_end + 2 {
+            return Ok(None);
+        }
+        body.extend_from_slice(&data[chunk_start..chunk_end]);
+        if &data[chunk_end..chunk_end + 2] != b"\r\n" {
+            bail!("Chunk is missing its trailing CRLF");
+        }
+
+        cursor = chunk_end + 2;
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Headers(BTreeMap<String, String>);
+
+impl Headers {
+    pub fn insert(&mut self, key: impl ToString, val: impl ToString) {
+        self.0
+            .insert(key.to_string().to_lowercase(), val.to_string());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.0.get(&key.to_lowercase())
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.0.contains_key(&key.to_lowercase())
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.0.remove(&key.to_lowercase());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
+}
+
+/// An HTTP request, either built by [`Client`] for sending or constructed
+/// directly (e.g. via [`Request::new`]) to encode as [`Request::encode_bhttp`].
+pub struct Request {
+    method: String,
+    scheme: String,
+    path: String,
+    headers: Headers,
+    body: Option<Vec<u8>>,
+}
+
+impl Request {
+    /// Builds a request directly, without going through [`Client`]. Useful
+    /// for producing a bhttp message (see [`Request::encode_bhttp`]) without
+    /// sending it over a connection.
+    pub fn new(
+        method: impl Into<String>,
+        scheme: impl Into<String>,
+        path: impl Into<String>,
+        headers: Headers,
+        body: Option<Vec<u8>>,
+    ) -> Self {
+        Self {
+            method: method.into(),
+            scheme: scheme.into(),
+            path: path.into(),
+            headers,
+            body,
+        }
+    }
+
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    pub fn body(&self) -> Option<&[u8]> {
+        self.body.as_deref()
+    }
+
+    pub fn send_v10(mut self, mut writer: impl Write) -> Result<()> {
+        let body = self.body.unwrap_or_default();
+        self.headers.insert("Content-Length", body.len());
+
+        writeln!(writer, "{} {} HTTP/1.0\r", self.method, self.path)?;
+
+        for (key, value) in self.headers.iter() {
+            writeln!(writer, "{key}: {value}\r")?;
+        }
+
+        writer.write_all(b"\r\n")?;
+
+        if !body.is_empty() {
+            writer.write_all(&body)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn send_v11(mut self, mut writer: impl Write) -> Result<()> {
+        let body = self.body.unwrap_or_default();
+        self.headers.insert("Transfer-Encoding", "chunked");
+
+        writeln!(writer, "{} {} HTTP/1.1\r", self.method, self.path)?;
+
+        for (key, value) in self.headers.iter() {
+            writeln!(writer, "{key}: {value}\r")?;
+        }
+
+        writer.write_all(b"\r\n")?;
+
+        if !body.is_empty() {
+            write!(writer, "{:x}\r\n", body.len())?;
+            writer.write_all(&body)?;
+            writer.write_all(b"\r\n")?;
+        }
+
+        writer.write_all(b"0\r\n\r\n")?;
+
+        Ok(())
+    }
+}
+
+pub struct Response {
+    status_code: u16,
+    status_message: String,
+    headers: Headers,
+    body: Vec<u8>,
+    decoded_body: Option<Vec<u8>>,
+    url: Option<Url>,
+    streaming: Option<streaming::StreamingState>,
+}
+
+impl Response {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let head = parse_head(bytes)?;
+        let mut body = Vec::new();
+
+        if head.is_chunked {
+            let mut body_slice = &bytes[head.pos..];
+            loop {
+                let mut parts = body_slice.split(|&c| c == b'\r');
+                let octets = parts.next().ok_or_else(|| anyhow!("Invalid chunk"))?;
+
+                assert_eq!(&body_slice[octets.len()..octets.len() + 2], b"\r\n");
+                let remaining = &body_slice[octets.len() + 2..];
+
+                let length: usize =
+                    usize::from_str_radix(&String::from_utf8(Vec::from(octets))?, 16)?;
+                if length == 0 {
+                    break;
+                }
+
+                let chunk = &remaining[..length];
+                body.extend_from_slice(chunk);
+
+                assert_eq!(&remaining[length..length + 2], b"\r\n");
+                body_slice = &remaining[length + 2..];
+            }
+        } else {
+            let mut content_length = head.content_length;
+            if content_length == 0 {
+                content_length = bytes.len().saturating_sub(head.pos);
+            }
+
+            body.extend_from_slice(&bytes[head.pos..head.pos + content_length]);
+        }
+
+        Ok(Response {
+            status_code: head.status_code,
+            status_message: head.status_message,
+            headers: head.headers,
+            body,
+            decoded_body: None,
+            url: None,
+            streaming: None,
+        })
+    }
+
+    fn set_url(&mut self, url: Url) {
+        self.url = Some(url);
+    }
+
+    fn set_decoded_body(&mut self, body: Vec<u8>) {
+        self.decoded_body = Some(body);
+    }
+
+    /// The final URL this response was received from, after following any
+    /// redirects.
+    pub fn url(&self) -> Option<&Url> {
+        self.url.as_ref()
+    }
+
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    pub fn status_message(&self) -> &str {
+        &self.status_message
+    }
+
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// The response body, transparently decompressed according to its
+    /// `Content-Encoding` if the client has decoding enabled. Falls back to
+    /// [`Response::raw_body`] when no decoding was applied.
+    pub fn body(&self) -> &[u8] {
+        self.decoded_body.as_deref().unwrap_or(&self.body)
+    }
+
+    /// The response body exactly as received on the wire, before any
+    /// `Content-Encoding` decompression.
+    pub fn raw_body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+enum ResponseParserState {
+    Status,
+    Header,
+}
+
+struct ParsedHead {
+    pos: usize,
+    status_code: u16,
+    status_message: String,
+    headers: Headers,
+    content_length: usize,
+    has_content_length: bool,
+    is_chunked: bool,
+}
+
+/// Parses the status line and headers from the start of `bytes`. `bytes` may
+/// contain only the head (as read incrementally off a live connection) or
+/// the head followed by the body (as in a fully-buffered response);
+/// `ParsedHead::pos` marks where the body begins either way.
+fn parse_head(bytes: &[u8]) -> Result<ParsedHead> {
+    let mut state = ResponseParserState::Status;
+
+    let mut status_code = 0u16;
+    let mut status_message = String::new();
+    let mut headers = Headers::default();
+    let mut content_length = 0usize;
+    let mut has_content_length = false;
+    let mut is_chunked = false;
+
+    let mut pos = 0;
+    for mut line in bytes.split(|&c| c == b'\n') {
+        pos += line.len() + 1;
+
+        if line.ends_with(b"\r") {
+            line = &line[..line.len() - 1];
+        }
+
+        match state {
+            ResponseParserState::Status => {
+                let line = String::from_utf8(Vec::from(line))?;
+                let parts: Vec<&str> = line.splitn(3, ' ').collect();
+                status_code = parts[1].parse()?;
+                status_message = parts[2].to_owned();
+                state = ResponseParserState::Header;
+                continue;
+            }
+            ResponseParserState::Header => {
+                if line.is_empty() {
+                    break;
+                }
+
+                let line = String::from_utf8(Vec::from(line))?;
+                let (key, value) = line
+                    .split_once(": ")
+                    .ok_or_else(|| anyhow!("Invalid header"))?;
+                headers.insert(key, value);
+                match key.to_lowercase().as_str() {
+                    "content-length" => {
+                        content_length = value.parse()?;
+                        has_content_length = true;
+                    }
+                    "transfer-encoding" => {
+                        if value.to_lowercase() != "chunked" {
+                            bail!("Unknown transfer-encoding value: {value}");
+                        }
+
+                        is_chunked = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(ParsedHead {
+        pos,
+        status_code,
+        status_message,
+        headers,
+        content_length,
+        has_content_length,
+        is_chunked,
+    })
+}
+
+fn prefix_lines(str: &str, prefix: &str) -> String {
+    str.lines()
+        .map(|line| format!("{prefix}{line}"))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::read_response;
+
+    #[test]
+    fn read_response_with_content_length_is_reusable() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+        let (_, response, reusable) = read_response(&mut Cursor::new(raw.as_slice())).unwrap();
+
+        assert_eq!(response.raw_body(), b"hello");
+        assert!(reusable);
+    }
+
+    #[test]
+    fn read_response_chunked_is_reusable() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+        let (_, response, reusable) = read_response(&mut Cursor::new(raw.as_slice())).unwrap();
+
+        assert_eq!(response.raw_body(), b"hello");
+        assert!(reusable);
+    }
+
+    #[test]
+    fn read_response_without_content_length_reads_to_eof_and_is_not_reusable() {
+        // No Content-Length and no Transfer-Encoding: chunked means the body
+        // is close-delimited, so it runs until the connection is closed.
+        let raw = b"HTTP/1.1 200 OK\r\n\r\nhello, world";
+        let (_, response, reusable) = read_response(&mut Cursor::new(raw.as_slice())).unwrap();
+
+        assert_eq!(response.raw_body(), b"hello, world");
+        assert!(!reusable);
+    }
+}
@@ -0,0 +1,151 @@
+//! Builds `multipart/form-data` request bodies, for
+//! [`super::Client::post_multipart`].
+
+enum Part {
+    Text { name: String, value: String },
+    File {
+        name: String,
+        filename: String,
+        content_type: String,
+        data: Vec<u8>,
+    },
+}
+
+/// Builds a `multipart/form-data` body out of text fields and file parts,
+/// generating a random boundary.
+#[derive(Default)]
+pub struct MultipartBuilder {
+    parts: Vec<Part>,
+}
+
+impl MultipartBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a plain text field.
+    pub fn add_text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parts.push(Part::Text {
+            name: name.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Adds a file field with the given filename, `Content-Type` and bytes.
+    pub fn add_file(
+        mut self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        data: Vec<u8>,
+    ) -> Self {
+        self.parts.push(Part::File {
+            name: name.into(),
+            filename: filename.into(),
+            content_type: content_type.into(),
+            data,
+        });
+        self
+    }
+
+    /// Serializes the accumulated parts into a body, returning it alongside
+    /// the `Content-Type` header value (including the boundary) to send it
+    /// with.
+    pub fn build(self) -> (Vec<u8>, String) {
+        let boundary = generate_boundary();
+        let mut body = Vec::new();
+
+        for part in &self.parts {
+            body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+            match part {
+                Part::Text { name, value } => {
+                    let name = escape_quoted(name);
+                    body.extend_from_slice(
+                        format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n").as_bytes(),
+                    );
+                    body.extend_from_slice(value.as_bytes());
+                }
+                Part::File {
+                    name,
+                    filename,
+                    content_type,
+                    data,
+                } => {
+                    let name = escape_quoted(name);
+                    let filename = escape_quoted(filename);
+                    let content_type = strip_crlf(content_type);
+                    body.extend_from_slice(
+                        format!(
+                            "Content-Disposition: form-data; name=\"{name}\"; filename=\"{filename}\"\r\n"
+                        )
+                        .as_bytes(),
+                    );
+                    body.extend_from_slice(format!("Content-Type: {content_type}\r\n\r\n").as_bytes());
+                    body.extend_from_slice(data);
+                }
+            }
+            body.extend_from_slice(b"\r\n");
+        }
+
+        body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+        (body, format!("multipart/form-data; boundary={boundary}"))
+    }
+}
+
+/// Strips CR/LF from `value` so it can't break out of its header line and
+/// inject another one.
+fn strip_crlf(value: &str) -> String {
+    value.chars().filter(|&c| c != '\r' && c != '\n').collect()
+}
+
+/// Prepares `value` for use as a `Content-Disposition` quoted-string
+/// parameter: backslash-escapes `\` and `"` (per RFC 6266) and strips
+/// CR/LF so it can't break out of the quotes or the header line.
+fn escape_quoted(value: &str) -> String {
+    strip_crlf(value).replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Generates a boundary that's exceedingly unlikely to collide with any of
+/// the parts' content, in the style curl and browsers use.
+fn generate_boundary() -> String {
+    use std::{collections::hash_map::RandomState, hash::BuildHasher, time::Instant};
+
+    let hash = RandomState::new().hash_one(Instant::now());
+    format!("------------------------{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filename_cannot_break_out_of_its_quotes_or_inject_a_header() {
+        let (body, _) = MultipartBuilder::new()
+            .add_file("file", "a\".rs\r\nX-Injected: y", "text/plain", b"data".to_vec())
+            .build();
+        let body = String::from_utf8_lossy(&body);
+
+        assert!(!body.contains("\r\nX-Injected"));
+        assert!(body.contains("filename=\"a\\\".rs"));
+    }
+
+    #[test]
+    fn text_field_name_with_quote_and_backslash_is_escaped() {
+        let (body, _) = MultipartBuilder::new().add_text("a\"\\b", "value").build();
+        let body = String::from_utf8_lossy(&body);
+
+        assert!(body.contains("name=\"a\\\"\\\\b\""));
+    }
+
+    #[test]
+    fn content_type_cannot_inject_a_header_via_crlf() {
+        let (body, _) = MultipartBuilder::new()
+            .add_file("file", "f.txt", "text/plain\r\nX-Injected: y", b"data".to_vec())
+            .build();
+        let body = String::from_utf8_lossy(&body);
+
+        assert!(!body.contains("\r\nX-Injected"));
+    }
+}
@@ -0,0 +1,109 @@
+//! Keeps idle HTTP/1.1 connections alive so they can be reused for a
+//! subsequent request to the same (scheme, host, port), similar to hyper's
+//! connection pool.
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    net::TcpStream,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+pub(crate) enum Connection {
+    Plain(TcpStream),
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Connection::Plain(stream) => stream.read(buf),
+            Connection::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Connection::Plain(stream) => stream.write(buf),
+            Connection::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Connection::Plain(stream) => stream.flush(),
+            Connection::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    pub scheme: String,
+    pub host: String,
+    pub port: u16,
+}
+
+struct IdleConnection {
+    connection: Connection,
+    idle_since: Instant,
+}
+
+pub(crate) struct Pool {
+    max_idle_per_host: usize,
+    idle_timeout: Duration,
+    idle: Mutex<HashMap<Key, Vec<IdleConnection>>>,
+}
+
+impl Pool {
+    pub(crate) fn new(max_idle_per_host: usize, idle_timeout: Duration) -> Self {
+        Self {
+            max_idle_per_host,
+            idle_timeout,
+            idle: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn set_max_idle_per_host(&mut self, max_idle_per_host: usize) {
+        self.max_idle_per_host = max_idle_per_host;
+    }
+
+    pub(crate) fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.idle_timeout = idle_timeout;
+    }
+
+    /// Takes an idle connection for `key` out of the pool, if one is still
+    /// fresh. Expired connections are dropped along the way.
+    pub(crate) fn checkout(&self, key: &Key) -> Option<Connection> {
+        let mut idle = self.idle.lock().unwrap();
+        let connections = idle.get_mut(key)?;
+
+        while let Some(entry) = connections.pop() {
+            if entry.idle_since.elapsed() < self.idle_timeout {
+                return Some(entry.connection);
+            }
+        }
+
+        None
+    }
+
+    /// Returns a connection to the pool for reuse, unless the host is
+    /// already at its idle connection limit.
+    pub(crate) fn checkin(&self, key: Key, connection: Connection) {
+        if self.max_idle_per_host == 0 {
+            return;
+        }
+
+        let mut idle = self.idle.lock().unwrap();
+        let connections = idle.entry(key).or_default();
+        if connections.len() < self.max_idle_per_host {
+            connections.push(IdleConnection {
+                connection,
+                idle_since: Instant::now(),
+            });
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Lazily reads a response body straight off a live connection instead of
+//! buffering it all in memory, for [`super::Client::send_streaming`].
+
+use std::io::{self, Read};
+
+use anyhow::{Result, bail};
+
+use super::{ParsedHead, Response, pool};
+
+enum Framing {
+    ContentLength(usize),
+    Chunked { finished: bool },
+    Eof,
+}
+
+pub(crate) struct StreamingState {
+    connection: pool::Connection,
+    /// Undigested bytes read off the connection, only used while
+    /// de-framing a chunked body.
+    raw_buffer: Vec<u8>,
+    /// Decoded body bytes ready to be handed to the caller.
+    decoded_queue: Vec<u8>,
+    framing: Framing,
+}
+
+impl StreamingState {
+    pub(crate) fn new(connection: pool::Connection, leftover: Vec<u8>, head: &ParsedHead) -> Self {
+        if head.is_chunked {
+            return Self {
+                connection,
+                raw_buffer: leftover,
+                decoded_queue: Vec::new(),
+                framing: Framing::Chunked { finished: false },
+            };
+        }
+
+        if head.has_content_length {
+            let take = leftover.len().min(head.content_length);
+            return Self {
+                connection,
+                raw_buffer: Vec::new(),
+                decoded_queue: leftover[..take].to_vec(),
+                framing: Framing::ContentLength(head.content_length - take),
+            };
+        }
+
+        Self {
+            connection,
+            raw_buffer: Vec::new(),
+            decoded_queue: leftover,
+            framing: Framing::Eof,
+        }
+    }
+}
+
+/// A [`std::io::Read`] handle onto a [`Response`]'s body, pulling bytes from
+/// its connection (and de-framing chunked transfer-encoding) on demand.
+pub struct BodyReader<'a>(&'a mut StreamingState);
+
+impl Read for BodyReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let state = &mut *self.0;
+
+        loop {
+            if !state.decoded_queue.is_empty() {
+                let n = buf.len().min(state.decoded_queue.len());
+                buf[..n].copy_from_slice(&state.decoded_queue[..n]);
+                state.decoded_queue.drain(..n);
+                return Ok(n);
+            }
+
+            match &mut state.framing {
+                Framing::ContentLength(remaining) => {
+                    if *remaining == 0 {
+                        return Ok(0);
+                    }
+                    let to_read = buf.len().min(*remaining);
+                    let n = state.connection.read(&mut buf[..to_read])?;
+                    if n == 0 {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "connection closed before the response body was complete",
+                        ));
+                    }
+                    *remaining -= n;
+                    return Ok(n);
+                }
+                Framing::Eof => return state.connection.read(buf),
+                Framing::Chunked { finished } => {
+                    if *finished {
+                        return Ok(0);
+                    }
+
+                    match try_extract_one_chunk(&mut state.raw_buffer) {
+                        Ok(Some(data)) if data.is_empty() => {
+                            *finished = true;
+                            return Ok(0);
+                        }
+                        Ok(Some(data)) => {
+                            state.decoded_queue = data;
+                        }
+                        Ok(None) => {
+                            let mut tmp = [0u8; 4096];
+                            let n = state.connection.read(&mut tmp)?;
+                            if n == 0 {
+                                return Err(io::Error::new(
+                                    io::ErrorKind::UnexpectedEof,
+                                    "connection closed before the chunked response body was complete",
+                                ));
+                            }
+                            state.raw_buffer.extend_from_slice(&tmp[..n]);
+                        }
+                        Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tries to pull one fully-buffered chunk out of the front of `raw`,
+/// consuming its framing bytes. Returns `Ok(Some(vec![]))` for the
+/// terminating zero-length chunk, `Ok(None)` if `raw` doesn't yet contain a
+/// complete next chunk.
+fn try_extract_one_chunk(raw: &mut Vec<u8>) -> Result<Option<Vec<u8>>> {
+    let Some(line_len) = raw.windows(2).position(|window| window == b"\r\n") else {
+        return Ok(None);
+    };
+    let octets = std::str::from_utf8(&raw[..line_len])?;
+    let length = usize::from_str_radix(octets.trim(), 16)?;
+    let chunk_start = line_len + 2;
+
+    if length == 0 {
+        let trailer_end = chunk_start + 2;
+        if raw.len() < trailer_end {
+            return Ok(None);
+        }
+        if &raw[chunk_start..trailer_end] != b"\r\n" {
+            bail!("Chunked response body is missing its trailer terminator");
+        }
+        raw.drain(..trailer_end);
+        return Ok(Some(Vec::new()));
+    }
+
+    let chunk_end = chunk_start + length;
+    if raw.len() < chunk_end + 2 {
+        return Ok(None);
+    }
+    if &raw[chunk_end..chunk_end + 2] != b"\r\n" {
+        bail!("Chunk is missing its trailing CRLF");
+    }
+
+    let data = raw[chunk_start..chunk_end].to_vec();
+    raw.drain(..chunk_end + 2);
+    Ok(Some(data))
+}
+
+pub(crate) fn read_response_head(mut connection: pool::Connection) -> Result<Response> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    while super::find_header_terminator(&buf).is_none() {
+        let n = connection.read(&mut chunk)?;
+        if n == 0 {
+            bail!("Connection closed before the response headers were complete");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = super::parse_head(&buf)?;
+    let leftover = buf[head.pos..].to_vec();
+    let state = StreamingState::new(connection, leftover, &head);
+
+    Ok(Response {
+        status_code: head.status_code,
+        status_message: head.status_message,
+        headers: head.headers,
+        body: Vec::new(),
+        decoded_body: None,
+        url: None,
+        streaming: Some(state),
+    })
+}
+
+impl Response {
+    /// A [`std::io::Read`] handle onto this response's body. Only available
+    /// on responses returned by [`super::Client::send_streaming`].
+    pub fn body_reader(&mut self) -> Result<BodyReader<'_>> {
+        let state = self
+            .streaming
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Response does not have a streaming body; use Client::send_streaming"))?;
+        Ok(BodyReader(state))
+    }
+}
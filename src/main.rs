@@ -1,6 +1,5 @@
 use anyhow::Result;
-
-mod http;
+use learning_http::http;
 
 fn main() -> Result<()> {
     let client = http::Client::new(http::HttpVersion::Http1_1);